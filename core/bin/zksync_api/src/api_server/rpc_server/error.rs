@@ -11,6 +11,7 @@ pub enum RpcErrorCodes {
     IncorrectTx = 103,
     FeeTooLow = 104,
     InappropriateFeeToken = 105,
+    AlreadyKnown = 106,
 
     MissingEthSignature = 200,
     EIP1271SignatureVerificationFail = 201,
@@ -41,6 +42,7 @@ impl From<TxAddError> for RpcErrorCodes {
             TxAddError::BatchTooBig => Self::Other,
             TxAddError::BatchWithdrawalsOverload => Self::Other,
             TxAddError::EthSignaturesLimitExceeded => Self::Other,
+            TxAddError::AlreadyKnown => Self::AlreadyKnown,
         }
     }
 }
@@ -64,6 +64,12 @@ impl MempoolBlocksHandler {
             .get_transaction_queue(executed_txs)
             .await?;
 
+        let stats = tx_queue.stats();
+        metrics::gauge!("mempool.l2_transactions", stats.l2_transactions as f64);
+        metrics::gauge!("mempool.l1_transactions", stats.l1_transactions as f64);
+        metrics::gauge!("mempool.accounts", stats.accounts as f64);
+        metrics::gauge!("mempool.max_account_depth", stats.max_account_depth as f64);
+
         let (txs, priority_ops, chunks_left) = tx_queue
             .select_transactions(
                 self.max_block_size_chunks,
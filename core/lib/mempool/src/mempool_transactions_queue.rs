@@ -1,9 +1,9 @@
 use crate::MempoolState;
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, VecDeque};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
 use zksync_types::mempool::SignedTxVariant;
 use zksync_types::tx::error::TxAddError;
-use zksync_types::PriorityOp;
+use zksync_types::{AccountId, Nonce, PriorityOp};
 
 #[derive(Debug, Clone)]
 struct MempoolPendingTransaction {
@@ -35,6 +35,19 @@ impl PartialOrd for MempoolPendingTransaction {
     }
 }
 
+/// Size of the transactions queue loaded for a block proposal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct MempoolStats {
+    /// Number of L2 transactions, with every transaction of a batch counted separately.
+    pub l2_transactions: usize,
+    /// Number of confirmed priority operations.
+    pub l1_transactions: usize,
+    /// Number of distinct accounts with L2 transactions in the queue.
+    pub accounts: usize,
+    /// Largest number of L2 transactions queued by a single account.
+    pub max_account_depth: usize,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct MempoolTransactionsQueue {
     /// Transactions ready for execution.
@@ -63,6 +76,39 @@ impl MempoolTransactionsQueue {
         res
     }
 
+    pub(crate) fn stats(&self) -> MempoolStats {
+        let mut account_depths: HashMap<AccountId, usize> = HashMap::new();
+        let mut l2_transactions = 0;
+
+        let pending_l2_transactions = self
+            .pending_l2_transactions
+            .iter()
+            .map(|pending| &pending.tx);
+        for tx_variant in self
+            .ready_l2_transactions
+            .iter()
+            .chain(pending_l2_transactions)
+        {
+            let txs = match tx_variant {
+                SignedTxVariant::Tx(tx) => std::slice::from_ref(tx),
+                SignedTxVariant::Batch(batch) => batch.txs.as_slice(),
+            };
+            for tx in txs {
+                l2_transactions += 1;
+                if let Ok(account_id) = tx.account_id() {
+                    *account_depths.entry(account_id).or_default() += 1;
+                }
+            }
+        }
+
+        MempoolStats {
+            l2_transactions,
+            l1_transactions: self.l1_transactions.len(),
+            accounts: account_depths.len(),
+            max_account_depth: account_depths.values().copied().max().unwrap_or(0),
+        }
+    }
+
     fn pop_l2_transactions_front(&mut self) -> Option<SignedTxVariant> {
         self.ready_l2_transactions.pop_front()
     }
@@ -174,6 +220,39 @@ impl MempoolTransactionsQueue {
         (max_block_size_chunks - used_chunks, result)
     }
 
+    /// Returns the next nonces of the accounts of `tx` once it is executed, or `None` if
+    /// one of its transactions has a nonce above the next one and can't be executed yet.
+    async fn next_nonces_after(
+        tx: &SignedTxVariant,
+        next_nonces: &HashMap<AccountId, Nonce>,
+        mempool_state: &MempoolState,
+    ) -> Result<Option<HashMap<AccountId, Nonce>>, TxAddError> {
+        let mut updated_nonces = HashMap::new();
+        for tx in tx.get_transactions() {
+            let account_id = match tx.account_id() {
+                Ok(account_id) => account_id,
+                Err(_) => continue,
+            };
+            let next_nonce = match updated_nonces
+                .get(&account_id)
+                .or_else(|| next_nonces.get(&account_id))
+            {
+                Some(nonce) => *nonce,
+                None => mempool_state.account_nonce(account_id).await?,
+            };
+            if tx.nonce() > next_nonce {
+                return Ok(None);
+            }
+            // A nonce below the next one will be rejected by the state keeper
+            // and doesn't move the account's nonce.
+            updated_nonces.insert(
+                account_id,
+                next_nonce.max(Nonce(tx.nonce().saturating_add(1))),
+            );
+        }
+        Ok(Some(updated_nonces))
+    }
+
     /// Collect txs depending on the remaining chunks size
     async fn select_l2_transactions(
         &mut self,
@@ -184,12 +263,22 @@ impl MempoolTransactionsQueue {
         self.prepare_new_ready_l2_transactions(block_timestamp);
 
         let mut txs_for_commit = Vec::new();
+        // Next nonces of the accounts seen so far, including the selected transactions.
+        let mut next_nonces = HashMap::new();
 
         while let Some(tx) = self.pop_l2_transactions_front() {
+            let updated_nonces =
+                match Self::next_nonces_after(&tx, &next_nonces, mempool_state).await? {
+                    Some(updated_nonces) => updated_nonces,
+                    // The transaction follows a nonce gap. It stays in the database
+                    // and will be proposed again once the gap is filled.
+                    None => continue,
+                };
             let chunks_for_tx = mempool_state.required_chunks(&tx).await?;
             if chunks_left >= chunks_for_tx {
                 txs_for_commit.push(tx);
                 chunks_left -= chunks_for_tx;
+                next_nonces.extend(updated_nonces);
             } else {
                 // We don't push back transactions because the transaction queue is used only once
                 break;
@@ -203,6 +292,8 @@ impl MempoolTransactionsQueue {
 mod tests {
     use chrono::Utc;
 
+    use zksync_storage::ConnectionPool;
+    use zksync_types::mempool::SignedTxsBatch;
     use zksync_types::tx::{TimeRange, Transfer, Withdraw};
     use zksync_types::{
         AccountId, Address, Deposit, Nonce, SignedZkSyncTx, TokenId, ZkSyncPriorityOp, ZkSyncTx,
@@ -210,24 +301,24 @@ mod tests {
 
     use super::*;
 
-    fn get_transfer_with_timestamps(valid_from: u64, valid_until: u64) -> SignedTxVariant {
+    fn get_transfer(account_id: u32, nonce: u32, time_range: TimeRange) -> SignedZkSyncTx {
         let transfer = Transfer::new(
-            AccountId(4242),
+            AccountId(account_id),
             Address::random(),
             Address::random(),
             TokenId(0),
             500u32.into(),
             20u32.into(),
-            Nonce(11),
-            TimeRange::new(valid_from, valid_until),
+            Nonce(nonce),
+            time_range,
             None,
         );
 
-        SignedTxVariant::Tx(SignedZkSyncTx {
+        SignedZkSyncTx {
             tx: ZkSyncTx::Transfer(Box::new(transfer)),
             eth_sign_data: None,
             created_at: Utc::now(),
-        })
+        }
     }
 
     fn get_withdraw() -> SignedTxVariant {
@@ -363,8 +454,8 @@ mod tests {
         };
 
         let withdraw0 = get_withdraw();
-        let transfer1 = get_transfer_with_timestamps(5, 13);
-        let transfer2 = get_transfer_with_timestamps(10, 15);
+        let transfer1: SignedTxVariant = get_transfer(4242, 11, TimeRange::new(5, 13)).into();
+        let transfer2: SignedTxVariant = get_transfer(4242, 11, TimeRange::new(10, 15)).into();
 
         // Insert transactions to the mempool transcations queue
         {
@@ -416,4 +507,93 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_mempool_stats() {
+        let batch = SignedTxsBatch {
+            txs: vec![
+                get_transfer(2, 0, Default::default()),
+                get_transfer(3, 0, Default::default()),
+            ],
+            batch_id: 1,
+            eth_signatures: Vec::new(),
+        };
+        let l2_transactions = vec![
+            get_transfer(1, 0, Default::default()).into(),
+            get_transfer(1, 1, Default::default()).into(),
+            SignedTxVariant::Batch(batch),
+            get_transfer(1, 2, Default::default()).into(),
+            // Not ready yet, but still counted.
+            get_transfer(4242, 11, TimeRange::new(u64::MAX - 1, u64::MAX)).into(),
+        ];
+        let mut transactions_queue =
+            MempoolTransactionsQueue::new(VecDeque::new(), l2_transactions.into());
+        transactions_queue.prepare_new_ready_l2_transactions(0);
+
+        assert_eq!(
+            transactions_queue.stats(),
+            MempoolStats {
+                l2_transactions: 6,
+                l1_transactions: 0,
+                accounts: 4,
+                max_account_depth: 3,
+            }
+        );
+    }
+
+    /// Runs the L2 selection over `txs` and returns the account and nonce of every
+    /// selected transaction.
+    async fn select_nonces(
+        txs: Vec<SignedZkSyncTx>,
+        mempool_state: &MempoolState,
+    ) -> Vec<(AccountId, Nonce)> {
+        let l2_transactions = txs.into_iter().map(SignedTxVariant::from).collect();
+        let mut transactions_queue =
+            MempoolTransactionsQueue::new(VecDeque::new(), l2_transactions);
+        let (_, selected) = transactions_queue
+            .select_l2_transactions(100, 0, mempool_state)
+            .await
+            .unwrap();
+
+        let mut nonces: Vec<_> = selected
+            .iter()
+            .flat_map(|tx| tx.get_transactions())
+            .map(|tx| (tx.account_id().unwrap(), tx.nonce()))
+            .collect();
+        nonces.sort();
+        nonces
+    }
+
+    /// Checks that a transaction following a nonce gap is not selected until the gap is filled.
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db_test"), ignore)]
+    async fn test_nonce_gap_held_back() {
+        // Neither account exists in the database, so both expect nonce 0 next.
+        let mempool_state = MempoolState::new(ConnectionPool::new(Some(1)));
+
+        let txs = vec![
+            get_transfer(9001, 0, Default::default()),
+            get_transfer(9001, 2, Default::default()),
+            get_transfer(9002, 0, Default::default()),
+        ];
+        assert_eq!(
+            select_nonces(txs, &mempool_state).await,
+            vec![(AccountId(9001), Nonce(0)), (AccountId(9002), Nonce(0))]
+        );
+
+        // Once the missing nonce arrives, the whole sequence is selected.
+        let txs = vec![
+            get_transfer(9001, 2, Default::default()),
+            get_transfer(9001, 0, Default::default()),
+            get_transfer(9001, 1, Default::default()),
+        ];
+        assert_eq!(
+            select_nonces(txs, &mempool_state).await,
+            vec![
+                (AccountId(9001), Nonce(0)),
+                (AccountId(9001), Nonce(1)),
+                (AccountId(9001), Nonce(2)),
+            ]
+        );
+    }
 }
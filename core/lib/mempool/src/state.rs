@@ -5,7 +5,7 @@ use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     mempool::{SignedTxVariant, SignedTxsBatch},
     tx::{error::TxAddError, TxHash},
-    Address, TransferOp, TransferToNewOp, ZkSyncTx,
+    AccountId, Address, Nonce, TransferOp, TransferToNewOp, ZkSyncTx,
 };
 
 use crate::MempoolTransactionsQueue;
@@ -98,6 +98,23 @@ impl MempoolState {
         }
     }
 
+    /// Returns the nonce the next transaction of the account must have, according to
+    /// the last state persisted by the committer.
+    pub async fn account_nonce(&self, account_id: AccountId) -> Result<Nonce, TxAddError> {
+        let nonce = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(|_| TxAddError::DbError)?
+            .chain()
+            .account_schema()
+            .estimate_nonce(account_id)
+            .await
+            .map_err(|_| TxAddError::DbError)?
+            .unwrap_or_default();
+        Ok(nonce)
+    }
+
     pub async fn collect_garbage(&self) {
         let mut storage = self.db_pool.access_storage().await.expect("Db error");
         // Remove any possible duplicates of already executed transactions
@@ -1,7 +1,9 @@
+use std::time::Instant;
+
 use futures::channel::{mpsc, oneshot};
 use futures::StreamExt;
 
-use zksync_storage::ConnectionPool;
+use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     mempool::SignedTxsBatch,
     tx::{error::TxAddError, TxEthSignature},
@@ -34,6 +36,19 @@ pub enum MempoolTransactionRequest {
     ),
 }
 
+/// Records the admission latency into the `mempool.add_tx` histogram, labelled by
+/// the kind of admission (`tx` or `batch`) and its outcome (`accepted` or `rejected`).
+/// For both kinds the measured span starts once a storage connection is acquired,
+/// so failures to acquire one are not recorded.
+fn record_admission(start: Instant, kind: &'static str, result: &Result<(), TxAddError>) {
+    let outcome = if result.is_ok() {
+        "accepted"
+    } else {
+        "rejected"
+    };
+    metrics::histogram!("mempool.add_tx", start.elapsed(), "type" => kind, "result" => outcome);
+}
+
 pub(crate) struct MempoolTransactionsHandler {
     pub db_pool: ConnectionPool,
     pub mempool_state: MempoolState,
@@ -43,13 +58,31 @@ pub(crate) struct MempoolTransactionsHandler {
 
 impl MempoolTransactionsHandler {
     async fn add_tx(&mut self, tx: SignedZkSyncTx) -> Result<(), TxAddError> {
-        // Correctness should be checked by `signature_checker`, thus
-        // `tx.check_correctness()` is not invoked here.
         let mut storage = self.db_pool.access_storage().await.map_err(|err| {
             vlog::error!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
 
+        Self::admit_tx(&mut storage, tx).await
+    }
+
+    /// Checks the transaction against the current state and stores it in the mempool.
+    async fn admit_tx(
+        storage: &mut StorageProcessor<'_>,
+        tx: SignedZkSyncTx,
+    ) -> Result<(), TxAddError> {
+        let start = Instant::now();
+        let result = Self::validate_and_insert_tx(storage, tx).await;
+        record_admission(start, "tx", &result);
+        result
+    }
+
+    async fn validate_and_insert_tx(
+        storage: &mut StorageProcessor<'_>,
+        tx: SignedZkSyncTx,
+    ) -> Result<(), TxAddError> {
+        // Correctness should be checked by `signature_checker`, thus
+        // `tx.check_correctness()` is not invoked here.
         let nonce = storage
             .chain()
             .account_schema()
@@ -63,7 +96,9 @@ impl MempoolTransactionsHandler {
             return Err(TxAddError::NonceMismatch);
         }
 
-        storage
+        // `mempool_txs` has no unique constraint on the hash, so `insert_tx` skips the
+        // insertion of an exact resubmission and reports it instead.
+        let inserted = storage
             .chain()
             .mempool_schema()
             .insert_tx(&tx)
@@ -72,6 +107,9 @@ impl MempoolTransactionsHandler {
                 vlog::error!("Mempool storage access error: {}", err);
                 TxAddError::DbError
             })?;
+        if !inserted {
+            return Err(TxAddError::AlreadyKnown);
+        }
 
         let labels = vec![
             ("stage", "mempool".to_string()),
@@ -137,6 +175,25 @@ impl MempoolTransactionsHandler {
         &mut self,
         txs: Vec<SignedZkSyncTx>,
         eth_signatures: Vec<TxEthSignature>,
+    ) -> Result<(), TxAddError> {
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+
+        let start = Instant::now();
+        let result = self
+            .validate_and_insert_batch(&mut storage, txs, eth_signatures)
+            .await;
+        record_admission(start, "batch", &result);
+        result
+    }
+
+    async fn validate_and_insert_batch(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        txs: Vec<SignedZkSyncTx>,
+        eth_signatures: Vec<TxEthSignature>,
     ) -> Result<(), TxAddError> {
         let batch = SignedTxsBatch {
             txs: txs.clone(),
@@ -144,11 +201,6 @@ impl MempoolTransactionsHandler {
             eth_signatures: eth_signatures.clone(),
         };
 
-        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
-            vlog::error!("Mempool storage access error: {}", err);
-            TxAddError::DbError
-        })?;
-
         for tx in txs.iter() {
             // Correctness should be checked by `signature_checker`, thus
             // `tx.check_correctness()` is not invoked here.
@@ -164,6 +216,18 @@ impl MempoolTransactionsHandler {
             if tx.nonce() < nonce {
                 return Err(TxAddError::NonceMismatch);
             }
+
+            // Unlike `insert_tx`, `insert_batch` does not skip known hashes, so this check
+            // is separate from the insertion and two concurrent submissions may both pass it.
+            let already_known = storage
+                .chain()
+                .mempool_schema()
+                .contains_tx(tx.hash())
+                .await
+                .map_err(|_| TxAddError::DbError)?;
+            if already_known {
+                return Err(TxAddError::AlreadyKnown);
+            }
         }
 
         if self.mempool_state.chunks_for_batch(&batch).await? > self.max_block_size_chunks {
@@ -213,3 +277,146 @@ impl MempoolTransactionsHandler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::sync::Once;
+
+    use chrono::Utc;
+    use db_test_macro::test as db_test;
+    use metrics::{GaugeValue, Key, Recorder, Unit};
+
+    use zksync_types::{tx::Transfer, AccountId, Address, Nonce, TokenId, ZkSyncTx};
+
+    use super::*;
+
+    thread_local! {
+        static RECORDED_HISTOGRAMS: RefCell<Vec<(Key, f64)>> = RefCell::new(Vec::new());
+    }
+
+    /// Collects histogram values emitted on the current thread. `db_test` runs the test body
+    /// on a current-thread runtime, so concurrently running tests don't affect the result.
+    struct ThreadLocalRecorder;
+
+    impl Recorder for ThreadLocalRecorder {
+        fn register_counter(&self, _: &Key, _: Option<Unit>, _: Option<&'static str>) {}
+        fn register_gauge(&self, _: &Key, _: Option<Unit>, _: Option<&'static str>) {}
+        fn register_histogram(&self, _: &Key, _: Option<Unit>, _: Option<&'static str>) {}
+        fn increment_counter(&self, _: &Key, _: u64) {}
+        fn update_gauge(&self, _: &Key, _: GaugeValue) {}
+        fn record_histogram(&self, key: &Key, value: f64) {
+            RECORDED_HISTOGRAMS.with(|values| values.borrow_mut().push((key.clone(), value)));
+        }
+    }
+
+    /// Installs `ThreadLocalRecorder` as the global recorder, once per test binary.
+    fn install_thread_local_recorder() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            metrics::set_boxed_recorder(Box::new(ThreadLocalRecorder))
+                .expect("failed to install the test recorder");
+        });
+    }
+
+    /// Returns the admission latencies recorded on this thread with the given `result` label.
+    fn recorded_admissions(outcome: &str) -> Vec<f64> {
+        RECORDED_HISTOGRAMS.with(|values| {
+            values
+                .borrow()
+                .iter()
+                .filter(|(key, _)| {
+                    key.labels()
+                        .any(|label| label.key() == "result" && label.value() == outcome)
+                })
+                .map(|(_, value)| *value)
+                .collect()
+        })
+    }
+
+    /// Builds a transfer with fixed addresses, so that equal arguments yield equal hashes.
+    fn transfer(nonce: u32, amount: u32) -> SignedZkSyncTx {
+        let transfer = Transfer::new(
+            AccountId(4242),
+            Address::repeat_byte(0x11),
+            Address::repeat_byte(0x22),
+            TokenId(0),
+            amount.into(),
+            20u32.into(),
+            Nonce(nonce),
+            Default::default(),
+            None,
+        );
+
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Checks that a byte-identical resubmission is rejected as already known,
+    /// while a different transaction with the same nonce and a transaction with
+    /// the next nonce are still accepted.
+    #[db_test]
+    async fn test_duplicate_tx_rejected(mut storage: StorageProcessor<'_>) {
+        let tx = transfer(0, 500);
+        MempoolTransactionsHandler::admit_tx(&mut storage, tx.clone())
+            .await
+            .expect("new transaction must be accepted");
+
+        let result = MempoolTransactionsHandler::admit_tx(&mut storage, tx.clone()).await;
+        assert!(matches!(result, Err(TxAddError::AlreadyKnown)));
+
+        let same_nonce_tx = transfer(0, 600);
+        MempoolTransactionsHandler::admit_tx(&mut storage, same_nonce_tx.clone())
+            .await
+            .expect("different transaction with the same nonce must be accepted");
+
+        MempoolTransactionsHandler::admit_tx(&mut storage, transfer(1, 500))
+            .await
+            .expect("transaction with a new nonce must be accepted");
+
+        let stored_txs = storage
+            .chain()
+            .mempool_schema()
+            .load_txs(&[])
+            .await
+            .unwrap();
+        let stored_copies = |hash| {
+            stored_txs
+                .iter()
+                .filter(|stored| stored.hashes() == vec![hash])
+                .count()
+        };
+        assert_eq!(stored_copies(tx.hash()), 1);
+        assert_eq!(stored_copies(same_nonce_tx.hash()), 1);
+    }
+
+    /// Checks that every admission is recorded into the latency histogram with its outcome.
+    #[db_test]
+    async fn test_admission_latency_recorded(mut storage: StorageProcessor<'_>) {
+        install_thread_local_recorder();
+
+        let txs: Vec<_> = (10..15).map(|nonce| transfer(nonce, 500)).collect();
+        for tx in &txs {
+            MempoolTransactionsHandler::admit_tx(&mut storage, tx.clone())
+                .await
+                .expect("new transaction must be accepted");
+        }
+        // Resubmitting is rejected as a duplicate.
+        for tx in &txs[..2] {
+            MempoolTransactionsHandler::admit_tx(&mut storage, tx.clone())
+                .await
+                .unwrap_err();
+        }
+
+        let accepted = recorded_admissions("accepted");
+        let rejected = recorded_admissions("rejected");
+        assert_eq!(accepted.len(), 5);
+        assert_eq!(rejected.len(), 2);
+        for duration in accepted.iter().chain(&rejected) {
+            assert!(duration.is_finite() && *duration >= 0.0 && *duration < 60.0);
+        }
+    }
+}
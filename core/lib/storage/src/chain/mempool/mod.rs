@@ -241,7 +241,9 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
     }
 
     /// Adds a new transaction to the mempool schema.
-    pub async fn insert_tx(&mut self, tx_data: &SignedZkSyncTx) -> QueryResult<()> {
+    /// Returns `false` if a transaction with the same hash is already stored, in which
+    /// case nothing is inserted. The check and the insertion are done in one statement.
+    pub async fn insert_tx(&mut self, tx_data: &SignedZkSyncTx) -> QueryResult<bool> {
         let start = Instant::now();
         let tx_hash = hex::encode(tx_data.tx.hash().as_ref());
         let tx = serde_json::to_value(&tx_data.tx)?;
@@ -252,9 +254,10 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .as_ref()
             .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
 
-        sqlx::query!(
+        let inserted_rows = sqlx::query!(
             "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-            VALUES ($1, $2, $3, $4, $5)",
+            SELECT $1, $2, $3, $4, $5
+            WHERE NOT EXISTS (SELECT 1 FROM mempool_txs WHERE tx_hash = $1)",
             tx_hash,
             tx,
             tx_data.created_at,
@@ -262,10 +265,11 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             batch_id,
         )
         .execute(self.0.conn())
-        .await?;
+        .await?
+        .rows_affected();
 
         metrics::histogram!("sql.chain.mempool.insert_tx", start.elapsed());
-        Ok(())
+        Ok(inserted_rows != 0)
     }
 
     pub async fn remove_tx(&mut self, tx: &[u8]) -> QueryResult<()> {
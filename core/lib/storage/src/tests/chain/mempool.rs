@@ -258,6 +258,21 @@ async fn remove_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that inserting an already stored tx is skipped and reported.
+#[db_test]
+async fn insert_tx_skips_known_hash(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(2);
+
+    assert!(MempoolSchema(&mut storage).insert_tx(&txs[0]).await?);
+    assert!(!MempoolSchema(&mut storage).insert_tx(&txs[0]).await?);
+    assert!(MempoolSchema(&mut storage).insert_tx(&txs[1]).await?);
+
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), txs.len());
+
+    Ok(())
+}
+
 /// Checks that already committed txs are removed by `collect_garbage` method.
 #[db_test]
 async fn collect_garbage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -57,6 +57,9 @@ pub enum TxAddError {
 
     #[error("Too many Ethereum signatures provided")]
     EthSignaturesLimitExceeded,
+
+    #[error("Transaction is already in the mempool")]
+    AlreadyKnown,
 }
 
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
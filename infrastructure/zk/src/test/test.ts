@@ -26,7 +26,7 @@ export async function db(reset: boolean, ...args: string[]) {
     await runOnTestDb(
         reset,
         'core/lib/storage',
-        `cargo test --release -p zksync_storage --lib -- --ignored --nocapture --test-threads=1
+        `cargo test --release -p zksync_storage -p zksync_mempool --lib -- --ignored --nocapture --test-threads=1
         ${args.join(' ')}`
     );
 }